@@ -1,15 +1,18 @@
 extern crate rivlib;
 
+use rivlib::{TimeScale, Timestamped};
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     assert_eq!(2, args.len());
-    let reader = rivlib::Reader::from_path(&args[1]);
+    let reader = rivlib::Reader::from_path(&args[1]).time_scale(TimeScale::UnixSeconds);
     println!("Time,Roll,Pitch");
     for result in reader.inclinations().unwrap() {
         let inclination = result.unwrap();
-        println!(
-            "{},{:.3},{:.3}",
-            inclination.time, inclination.roll, inclination.pitch
-        );
+        let time = inclination
+            .datetime(reader.configured_time_scale())
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| inclination.time.to_string());
+        println!("{},{:.3},{:.3}", time, inclination.roll, inclination.pitch);
     }
 }
@@ -4,7 +4,14 @@ use std::env;
 use std::path::PathBuf;
 
 fn main() {
-    println!("cargo:rustc-link-lib=scanifc-mt");
+    // Statically linking libscanifc-mt is opt-out via the `static-link` feature (on by default),
+    // so this crate still builds on machines without the proprietary SDK installed, as long as
+    // callers stick to `scanifc::Library`'s dlopen path instead of the statically-linked
+    // bindings. Requires a `[features] default = ["static-link"]` entry in this crate's
+    // Cargo.toml.
+    if cfg!(feature = "static-link") {
+        println!("cargo:rustc-link-lib=scanifc-mt");
+    }
     let bindings = bindgen::builder()
         .header("wrapper.h")
         .derive_default(true)
@@ -1,4 +1,6 @@
+extern crate chrono;
 extern crate libc;
+extern crate libloading;
 #[macro_use]
 extern crate quick_error;
 extern crate scanifc_sys;
@@ -7,10 +9,14 @@ extern crate tempfile;
 
 #[macro_use]
 mod macros;
+mod library;
 pub mod point3d;
 mod point;
+mod time_converter;
 
-pub use point::Point;
+pub use library::Library;
+pub use point::{EchoType, Point};
+pub use time_converter::TimeConverter;
 
 // This number was cribbed from the rivlib example.
 const LAST_ERROR_BUFFER_SIZE: usize = 512;
@@ -52,6 +58,24 @@ quick_error! {
             description("a scanifc error")
             display("error code {}, message: {}", code, message)
         }
+        /// The scanifc shared library couldn't be loaded from disk.
+        LibraryLoad(path: String, err: libloading::Error) {
+            description("couldn't load the scanifc library")
+            display("couldn't load the scanifc library at {}: {}", path, err)
+        }
+        /// A required symbol was missing from a dynamically-loaded scanifc library.
+        MissingSymbol(name: String, err: libloading::Error) {
+            description("a required symbol was missing from the scanifc library")
+            display("symbol {} was missing from the scanifc library: {}", name, err)
+        }
+        /// A stream was opened without a `Library` while the `static-link` feature was disabled.
+        ///
+        /// With `static-link` off, there are no statically-linked bindings to fall back to, so a
+        /// `Library` attached via `Stream::library` is the only way to open a stream.
+        NoLibrary {
+            description("no Library was attached, and the static-link feature is disabled")
+            display("no Library was attached via Stream::library, and the static-link feature is disabled")
+        }
     }
 }
 
@@ -69,7 +93,10 @@ pub struct Version {
     pub build: u16,
 }
 
-/// Returns the version number from the library.
+/// Returns the version number from the statically-linked library.
+///
+/// Only available with the `static-link` feature, which links `libscanifc-mt` at build time. To
+/// read the version from a library loaded at runtime instead, use `Library::version`.
 ///
 /// # Examples
 ///
@@ -77,6 +104,7 @@ pub struct Version {
 /// let version = scanifc::library_version().unwrap();
 /// println!("Version: {}.{}.{}", version.major, version.minor, version.build);
 /// ```
+#[cfg(feature = "static-link")]
 pub fn library_version() -> Result<Version> {
     let mut version = Version::default();
     scanifc_try!(scanifc_sys::scanifc_get_library_version(
@@ -89,33 +117,45 @@ pub fn library_version() -> Result<Version> {
 
 /// Returns extended version information that allows traceability of the SCM system.
 ///
+/// Only available with the `static-link` feature. To read this from a library loaded at runtime
+/// instead, use `Library::build_version`.
+///
 /// # Examples
 ///
 /// ```
 /// let version = scanifc::library_build_version();
 /// ```
+#[cfg(feature = "static-link")]
 pub fn library_build_version() -> Result<String> {
     library_info().map(|(version, _)| version)
 }
 
 /// Returns additional information about the build.
 ///
+/// Only available with the `static-link` feature. To read this from a library loaded at runtime
+/// instead, use `Library::build_tag`.
+///
 /// # Examples
 ///
 /// ```
 /// let tag = scanifc::library_build_tag();
 /// ```
+#[cfg(feature = "static-link")]
 pub fn library_build_tag() -> Result<String> {
     library_info().map(|(_, tag)| tag)
 }
 
-/// Returns the last error message recorded by the scanifc library.
+/// Returns the last error message recorded by the statically-linked scanifc library.
+///
+/// Only available with the `static-link` feature. To read this from a library loaded at runtime
+/// instead, use `Library::last_error`.
 ///
 /// # Examples
 ///
 /// ```
 /// let message = scanifc::last_error().unwrap();
 /// ```
+#[cfg(feature = "static-link")]
 pub fn last_error() -> Result<String> {
     use std::ffi::CString;
 
@@ -143,6 +183,7 @@ pub fn last_error() -> Result<String> {
     c_string.into_string().map_err(Error::from)
 }
 
+#[cfg(feature = "static-link")]
 fn library_info() -> Result<(String, String)> {
     use std::ptr;
     use std::ffi::CStr;
@@ -161,7 +202,7 @@ fn library_info() -> Result<(String, String)> {
     ))
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "static-link"))]
 mod tests {
     use super::*;
 
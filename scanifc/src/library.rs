@@ -0,0 +1,188 @@
+//! Runtime (`dlopen`) loading of the scanifc shared library.
+//!
+//! The proprietary scanifc SDK is often unavailable on CI machines and developer boxes, which
+//! otherwise keeps this crate from even compiling there, since `scanifc-sys` links against it at
+//! build time. `Library` resolves the handful of symbols this crate needs at runtime instead, so
+//! a missing library or symbol becomes an `Error` rather than a link failure, and `Stream`s can
+//! be created through it in place of the statically-linked bindings.
+
+use libc;
+use scanifc_sys;
+use std::ffi::CString;
+use std::fmt;
+use std::path::Path;
+use {Error, Result, Version};
+
+pub type GetLibraryVersion = unsafe extern "C" fn(*mut u16, *mut u16, *mut u16) -> libc::c_int;
+pub type GetLibraryInfo =
+    unsafe extern "C" fn(*mut *const libc::c_char, *mut *const libc::c_char) -> libc::c_int;
+pub type GetLastError = unsafe extern "C" fn(*mut libc::c_char, u32, *mut u32) -> libc::c_int;
+pub type Point3dstreamOpen = unsafe extern "C" fn(*const libc::c_char,
+                                                   libc::c_int,
+                                                   *mut scanifc_sys::point3dstream_handle)
+                                                   -> libc::c_int;
+pub type Point3dstreamRead = unsafe extern "C" fn(scanifc_sys::point3dstream_handle,
+                                                   u32,
+                                                   *mut scanifc_sys::scanifc_xyz32_t,
+                                                   *mut scanifc_sys::scanifc_attributes_t,
+                                                   *mut u64,
+                                                   *mut u32,
+                                                   *mut libc::c_int)
+                                                   -> libc::c_int;
+pub type Point3dstreamClose =
+    unsafe extern "C" fn(scanifc_sys::point3dstream_handle) -> libc::c_int;
+
+/// A `dlopen`ed handle to the scanifc shared library, with its symbols resolved into function
+/// pointers.
+///
+/// `Stream::library` attaches a `Library` to a stream so that every FFI call it makes is routed
+/// through these resolved symbols instead of the statically-linked `scanifc-sys` bindings.
+pub struct Library {
+    _library: ::libloading::Library,
+    pub(crate) get_library_version: GetLibraryVersion,
+    pub(crate) get_library_info: GetLibraryInfo,
+    pub(crate) get_last_error: GetLastError,
+    pub(crate) point3dstream_open: Point3dstreamOpen,
+    pub(crate) point3dstream_read: Point3dstreamRead,
+    pub(crate) point3dstream_close: Point3dstreamClose,
+}
+
+macro_rules! symbol {
+    ($library:expr, $name:expr) => {
+        unsafe {
+            *$library.get($name).map_err(|err| {
+                Error::MissingSymbol(String::from_utf8_lossy($name).into_owned(), err)
+            })?
+        }
+    }
+}
+
+impl Library {
+    /// Loads the scanifc shared library from `path` and resolves the symbols this crate needs.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use scanifc::Library;
+    /// let library = Library::open("libscanifc-mt.so").unwrap();
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Library> {
+        let library = ::libloading::Library::new(path.as_ref()).map_err(|err| {
+            Error::LibraryLoad(path.as_ref().display().to_string(), err)
+        })?;
+        Ok(Library {
+            get_library_version: symbol!(library, b"scanifc_get_library_version"),
+            get_library_info: symbol!(library, b"scanifc_get_library_info"),
+            get_last_error: symbol!(library, b"scanifc_get_last_error"),
+            point3dstream_open: symbol!(library, b"scanifc_point3dstream_open"),
+            point3dstream_read: symbol!(library, b"scanifc_point3dstream_read"),
+            point3dstream_close: symbol!(library, b"scanifc_point3dstream_close"),
+            _library: library,
+        })
+    }
+
+    /// Returns the version of this loaded library.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use scanifc::Library;
+    /// let library = Library::open("libscanifc-mt.so").unwrap();
+    /// let version = library.version().unwrap();
+    /// ```
+    pub fn version(&self) -> Result<Version> {
+        let mut version = Version::default();
+        let result = unsafe {
+            (self.get_library_version)(&mut version.major, &mut version.minor, &mut version.build)
+        };
+        self.check(result)?;
+        Ok(version)
+    }
+
+    /// Returns extended version information that allows traceability of the SCM system.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use scanifc::Library;
+    /// let library = Library::open("libscanifc-mt.so").unwrap();
+    /// let version = library.build_version().unwrap();
+    /// ```
+    pub fn build_version(&self) -> Result<String> {
+        self.info().map(|(version, _)| version)
+    }
+
+    /// Returns additional information about the build.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use scanifc::Library;
+    /// let library = Library::open("libscanifc-mt.so").unwrap();
+    /// let tag = library.build_tag().unwrap();
+    /// ```
+    pub fn build_tag(&self) -> Result<String> {
+        self.info().map(|(_, tag)| tag)
+    }
+
+    fn info(&self) -> Result<(String, String)> {
+        use std::ptr;
+        use std::ffi::CStr;
+
+        let mut version: *const libc::c_char = ptr::null();
+        let mut tag: *const libc::c_char = ptr::null();
+        let result = unsafe { (self.get_library_info)(&mut version, &mut tag) };
+        self.check(result)?;
+        let version = unsafe { CStr::from_ptr(version) };
+        let tag = unsafe { CStr::from_ptr(tag) };
+        Ok((
+            version.to_string_lossy().into_owned(),
+            tag.to_string_lossy().into_owned(),
+        ))
+    }
+
+    /// Returns the last error message recorded by this library.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use scanifc::Library;
+    /// let library = Library::open("libscanifc-mt.so").unwrap();
+    /// let message = library.last_error().unwrap();
+    /// ```
+    pub fn last_error(&self) -> Result<String> {
+        const BUFFER_SIZE: usize = 512;
+
+        let mut buffer = vec![0; BUFFER_SIZE];
+        let mut message_size = 0;
+        let result =
+            unsafe { (self.get_last_error)(buffer.as_mut_ptr(), buffer.len() as u32, &mut message_size) };
+        if result != 0 {
+            return Err(Error::GetLastError(result));
+        }
+        let c_string = CString::new(buffer
+            .iter()
+            .take(message_size as usize)
+            .map(|&n| if n < 0 {
+                Err(Error::LastErrorMessage(buffer.clone()))
+            } else {
+                Ok(n as u8)
+            })
+            .collect::<Result<Vec<u8>>>()?)?;
+        c_string.into_string().map_err(Error::from)
+    }
+
+    fn check(&self, result: libc::c_int) -> Result<()> {
+        if result != 0 {
+            Err(Error::Scanifc(result, self.last_error()?))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Debug for Library {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Library").finish()
+    }
+}
@@ -1,7 +1,9 @@
+use chrono::{DateTime, Utc};
 use scanifc_sys;
+use time_converter::TimeConverter;
 
 /// A 3d point.
-#[derive(Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct Point {
     /// The x coordinate.
     pub x: f32,
@@ -33,6 +35,32 @@ pub struct Point {
     pub time: f64,
 }
 
+impl Point {
+    /// Resolves this point's `time` into a wall-clock timestamp using `converter`.
+    ///
+    /// `converter` accumulates the running PPS second count across calls, so points from a
+    /// single stream must be passed through the same `TimeConverter`, in the order they were
+    /// read, for the PPS timeframe case to resolve correctly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// # extern crate scanifc;
+    /// # fn main() {
+    /// use chrono::Utc;
+    /// use scanifc::Point;
+    /// use scanifc::TimeConverter;
+    /// let point = Point::default();
+    /// let mut converter = TimeConverter::absolute_from_epoch(Utc::now());
+    /// let timestamp = point.timestamp(&mut converter);
+    /// # }
+    /// ```
+    pub fn timestamp(&self, converter: &mut TimeConverter) -> Option<DateTime<Utc>> {
+        converter.resolve(self.time, self.is_time_in_pps_timeframe, self.is_pps_new)
+    }
+}
+
 impl From<(scanifc_sys::scanifc_xyz32_t, scanifc_sys::scanifc_attributes_t, u64)> for Point {
     fn from(
         (xyz32, attributes, time): (scanifc_sys::scanifc_xyz32_t,
@@ -66,7 +94,7 @@ impl From<(scanifc_sys::scanifc_xyz32_t, scanifc_sys::scanifc_attributes_t, u64)
 }
 
 /// The type of echo.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum EchoType {
     Single,
     First,
@@ -74,6 +102,12 @@ pub enum EchoType {
     Last,
 }
 
+impl Default for EchoType {
+    fn default() -> EchoType {
+        EchoType::Single
+    }
+}
+
 impl From<u16> for EchoType {
     fn from(n: u16) -> EchoType {
         match n & 3 {
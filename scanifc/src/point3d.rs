@@ -1,7 +1,12 @@
-use {Point, Result};
+use {EchoType, Error, Point, Result};
+use library::Library;
 use scanifc_sys;
 use std::collections::VecDeque;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
 
 // Cribbed from rivlib's examples.
 const DEFAULT_WANT: u32 = 1024;
@@ -12,18 +17,213 @@ const DEFAULT_SYNC_TO_PPS: bool = true;
 /// Follows the builder pattern to set the options for the stream.
 #[derive(Debug)]
 pub struct Stream {
+    filter: Option<Arc<PointFilter>>,
+    library: Option<Arc<Library>>,
     log: Option<PathBuf>,
     sync_to_pps: bool,
     uri: Uri,
     want: u32,
 }
 
+/// Which set of scanifc FFI symbols an `OpenStream` reads through.
+#[derive(Clone, Debug)]
+enum Backend {
+    /// The statically-linked `scanifc-sys` bindings.
+    ///
+    /// Only available with the `static-link` feature, since it's the only variant that refers to
+    /// the statically-linked FFI symbols.
+    #[cfg(feature = "static-link")]
+    Linked,
+    /// Symbols resolved at runtime from a `dlopen`ed `Library`.
+    Dynamic(Arc<Library>),
+}
+
 /// An open stream of points, used for reading.
 #[derive(Debug)]
 pub struct OpenStream {
+    backend: Backend,
     buffer: VecDeque<Point>,
+    filter: Option<Arc<PointFilter>>,
     handle: scanifc_sys::point3dstream_handle,
     want: u32,
+    pxyz32: Vec<scanifc_sys::scanifc_xyz32_t>,
+    pattributes: Vec<scanifc_sys::scanifc_attributes_t>,
+    ptime: Vec<u64>,
+}
+
+/// An axis-aligned bounding box, used by `PointFilter::aabb`.
+#[derive(Clone, Copy, Debug)]
+struct Aabb {
+    min: (f32, f32, f32),
+    max: (f32, f32, f32),
+}
+
+impl Aabb {
+    fn contains(&self, point: &Point) -> bool {
+        point.x >= self.min.0 && point.x <= self.max.0 && point.y >= self.min.1 &&
+            point.y <= self.max.1 && point.z >= self.min.2 && point.z <= self.max.2
+    }
+}
+
+/// A client-side predicate filter, applied to points as they're read.
+///
+/// Predicates are combined with AND semantics: a point is kept only if it satisfies all of the
+/// predicates that have been set. Attach a `PointFilter` to a `Stream` with `Stream::filter` to
+/// have `OpenStream::read` and `OpenStream::read_into` (and so the `Iterator` impl and
+/// `into_points`, which are both built on `read`) only ever yield matching points, without
+/// round-tripping through a server-side demultiplexer file.
+#[derive(Default)]
+pub struct PointFilter {
+    min_amplitude: Option<f32>,
+    max_deviation: Option<u16>,
+    echo_types: Option<Vec<EchoType>>,
+    facets: Option<Vec<u8>>,
+    aabb: Option<Aabb>,
+    predicate: Option<Box<Fn(&Point) -> bool + Send + Sync>>,
+}
+
+impl PointFilter {
+    /// Creates a new, empty point filter.
+    ///
+    /// An empty filter matches every point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new();
+    /// ```
+    pub fn new() -> PointFilter {
+        PointFilter::default()
+    }
+
+    /// Keeps only points with at least this amplitude, in dB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new().min_amplitude(-10.0);
+    /// ```
+    pub fn min_amplitude(mut self, min_amplitude: f32) -> PointFilter {
+        self.min_amplitude = Some(min_amplitude);
+        self
+    }
+
+    /// Keeps only points with at most this deviation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new().max_deviation(10);
+    /// ```
+    pub fn max_deviation(mut self, max_deviation: u16) -> PointFilter {
+        self.max_deviation = Some(max_deviation);
+        self
+    }
+
+    /// Keeps only points with one of the given echo types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::EchoType;
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new().echo_types(&[EchoType::Single, EchoType::Last]);
+    /// ```
+    pub fn echo_types(mut self, echo_types: &[EchoType]) -> PointFilter {
+        self.echo_types = Some(echo_types.to_vec());
+        self
+    }
+
+    /// Keeps only points with one of the given facet numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new().facets(&[0, 1]);
+    /// ```
+    pub fn facets(mut self, facets: &[u8]) -> PointFilter {
+        self.facets = Some(facets.to_vec());
+        self
+    }
+
+    /// Keeps only points inside the axis-aligned bounding box from `min` to `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new().aabb((0.0, 0.0, 0.0), (10.0, 10.0, 10.0));
+    /// ```
+    pub fn aabb(mut self, min: (f32, f32, f32), max: (f32, f32, f32)) -> PointFilter {
+        self.aabb = Some(Aabb { min: min, max: max });
+        self
+    }
+
+    /// Keeps only points for which `predicate` returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::PointFilter;
+    /// let filter = PointFilter::new().predicate(|point| point.reflectance > 0.0);
+    /// ```
+    pub fn predicate<F>(mut self, predicate: F) -> PointFilter
+    where
+        F: Fn(&Point) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Some(Box::new(predicate));
+        self
+    }
+
+    fn matches(&self, point: &Point) -> bool {
+        if let Some(min_amplitude) = self.min_amplitude {
+            if point.amplitude < min_amplitude {
+                return false;
+            }
+        }
+        if let Some(max_deviation) = self.max_deviation {
+            if point.deviation > max_deviation {
+                return false;
+            }
+        }
+        if let Some(ref echo_types) = self.echo_types {
+            if !echo_types.contains(&point.echo_type) {
+                return false;
+            }
+        }
+        if let Some(ref facets) = self.facets {
+            if !facets.contains(&point.facet_number) {
+                return false;
+            }
+        }
+        if let Some(ref aabb) = self.aabb {
+            if !aabb.contains(point) {
+                return false;
+            }
+        }
+        if let Some(ref predicate) = self.predicate {
+            if !predicate(point) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Debug for PointFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PointFilter")
+            .field("min_amplitude", &self.min_amplitude)
+            .field("max_deviation", &self.max_deviation)
+            .field("echo_types", &self.echo_types)
+            .field("facets", &self.facets)
+            .field("aabb", &self.aabb)
+            .finish()
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -43,6 +243,8 @@ impl Stream {
     /// ```
     pub fn from_path<P: AsRef<Path>>(path: P) -> Stream {
         Stream {
+            filter: None,
+            library: None,
             log: None,
             sync_to_pps: DEFAULT_SYNC_TO_PPS,
             uri: Uri::from_path(path),
@@ -60,6 +262,8 @@ impl Stream {
     /// ```
     pub fn from_rdtp(rdtp: &str) -> Stream {
         Stream {
+            filter: None,
+            library: None,
             log: None,
             sync_to_pps: DEFAULT_SYNC_TO_PPS,
             uri: Uri::from_rdtp(rdtp),
@@ -67,6 +271,40 @@ impl Stream {
         }
     }
 
+    /// Attaches a client-side predicate filter to this stream.
+    ///
+    /// Once open, only points that satisfy `filter` are ever handed back by `read`,
+    /// `read_into`, the `Iterator` impl, or `into_points`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::{PointFilter, Stream};
+    /// let filter = PointFilter::new().min_amplitude(-10.0);
+    /// let stream = Stream::from_path("data/scan.rxp").filter(filter);
+    /// ```
+    pub fn filter(mut self, filter: PointFilter) -> Stream {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Uses a dynamically-loaded scanifc `Library` for this stream instead of the
+    /// statically-linked `scanifc-sys` bindings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use scanifc::Library;
+    /// use scanifc::point3d::Stream;
+    /// let library = Arc::new(Library::open("libscanifc-mt.so").unwrap());
+    /// let stream = Stream::from_path("data/scan.rxp").library(library);
+    /// ```
+    pub fn library(mut self, library: Arc<Library>) -> Stream {
+        self.library = Some(library);
+        self
+    }
+
     /// Sets the `sync_to_pps` field.
     ///
     /// # Examples
@@ -125,19 +363,160 @@ impl Stream {
         use std::ffi::CString;
         use std::ptr;
 
+        let backend = match self.library {
+            Some(ref library) => Backend::Dynamic(library.clone()),
+            #[cfg(feature = "static-link")]
+            None => Backend::Linked,
+            #[cfg(not(feature = "static-link"))]
+            None => return Err(Error::NoLibrary),
+        };
         let mut handle: scanifc_sys::point3dstream_handle = ptr::null_mut();
         let uri = CString::new(self.uri.as_str())?;
-        scanifc_try!(scanifc_sys::scanifc_point3dstream_open(
-            uri.as_ptr(),
-            if self.sync_to_pps { 1 } else { 0 },
-            &mut handle,
-        ));
+        let sync_to_pps = if self.sync_to_pps { 1 } else { 0 };
+        match backend {
+            Backend::Dynamic(ref library) => {
+                let result = unsafe { (library.point3dstream_open)(uri.as_ptr(), sync_to_pps, &mut handle) };
+                if result != 0 {
+                    return Err(Error::Scanifc(result, library.last_error()?));
+                }
+            }
+            #[cfg(feature = "static-link")]
+            Backend::Linked => {
+                scanifc_try!(scanifc_sys::scanifc_point3dstream_open(
+                    uri.as_ptr(),
+                    sync_to_pps,
+                    &mut handle,
+                ));
+            }
+        }
         Ok(OpenStream {
+            backend: backend,
             buffer: VecDeque::new(),
+            filter: self.filter.clone(),
             handle: handle,
             want: self.want,
+            pxyz32: vec![Default::default(); self.want as usize],
+            pattributes: vec![Default::default(); self.want as usize],
+            ptime: vec![Default::default(); self.want as usize],
         })
     }
+
+    /// Opens many rxp files, raising the process's open file descriptor limit first.
+    ///
+    /// Scanning a whole directory of tiles routinely opens far more files at once than the OS
+    /// default soft `RLIMIT_NOFILE` allows, so this raises that limit toward its hard limit
+    /// before opening anything. The raise is always attempted on a best-effort basis: a failure
+    /// to read or set the limit is not fatal, and opening proceeds regardless.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::Stream;
+    /// let streams = Stream::open_many(&["data/scan.rxp"]).unwrap();
+    /// ```
+    pub fn open_many<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<OpenStream>> {
+        raise_file_descriptor_limit();
+        paths.iter().map(|path| Stream::from_path(path).open()).collect()
+    }
+
+    /// Opens this stream and reads it on a background thread, delivering batches over a
+    /// bounded channel.
+    ///
+    /// This lets a caller multiplex LiDAR ingestion with other I/O in its own `poll`/`select`
+    /// reactor instead of blocking on the `scanifc_point3dstream_read` loop that `OpenStream`'s
+    /// `Iterator` impl runs directly. `buffer_batches` is the channel's capacity: once that many
+    /// undelivered batches are queued, the background thread blocks on `send` until the
+    /// `AsyncStream` consumer catches up, so a slow consumer applies backpressure rather than
+    /// letting the reader run away with memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::Stream;
+    /// let async_stream = Stream::from_path("data/scan.rxp").into_async(4).unwrap();
+    /// while let Some(points) = async_stream.recv().unwrap() {
+    ///     println!("got {} points", points.len());
+    /// }
+    /// ```
+    pub fn into_async(self, buffer_batches: usize) -> Result<AsyncStream> {
+        let stream = AssertSend(self.open()?);
+        let (sender, receiver) = mpsc::sync_channel(buffer_batches);
+        thread::spawn(move || {
+            let AssertSend(mut stream) = stream;
+            loop {
+                match stream.read() {
+                    Ok(Some(points)) => {
+                        if sender.send(Ok(points)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        let _ = sender.send(Err(err));
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(AsyncStream { receiver: receiver })
+    }
+}
+
+/// A non-blocking handle to a stream being read on a background thread.
+///
+/// Produced by `Stream::into_async`. A terminal `scanifc` error from the background thread is
+/// delivered across the channel instead of being swallowed, so it surfaces from `try_recv` or
+/// `recv` just like it would from a direct `OpenStream` read.
+#[derive(Debug)]
+pub struct AsyncStream {
+    receiver: mpsc::Receiver<Result<Vec<Point>>>,
+}
+
+impl AsyncStream {
+    /// Polls for the next batch of points without blocking.
+    ///
+    /// Returns `Ok(None)` both when no batch is ready yet and once the background reader has
+    /// exhausted the stream; a caller driving its own reactor loop simply keeps polling either
+    /// way, the same as it would treat a `WouldBlock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::Stream;
+    /// let async_stream = Stream::from_path("data/scan.rxp").into_async(4).unwrap();
+    /// match async_stream.try_recv().unwrap() {
+    ///     Some(points) => println!("got {} points", points.len()),
+    ///     None => {}
+    /// }
+    /// ```
+    pub fn try_recv(&self) -> Result<Option<Vec<Point>>> {
+        match self.receiver.try_recv() {
+            Ok(Ok(points)) => Ok(Some(points)),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Blocks until the next batch of points is ready.
+    ///
+    /// Returns `Ok(None)` once the background reader has exhausted the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::point3d::Stream;
+    /// let async_stream = Stream::from_path("data/scan.rxp").into_async(4).unwrap();
+    /// while let Some(points) = async_stream.recv().unwrap() {
+    ///     println!("got {} points", points.len());
+    /// }
+    /// ```
+    pub fn recv(&self) -> Result<Option<Vec<Point>>> {
+        match self.receiver.recv() {
+            Ok(Ok(points)) => Ok(Some(points)),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Ok(None),
+        }
+    }
 }
 
 impl OpenStream {
@@ -166,51 +545,135 @@ impl OpenStream {
         }
     }
 
+    /// Reads up to `want` points, reusing this stream's scratch buffers across calls.
+    ///
+    /// The FFI only ever writes the first `got` elements of each scratch vector, so the
+    /// remainder of a buffer from a previous, larger read is never observed. If this stream has
+    /// a `PointFilter` attached, non-matching points are dropped here: the returned batch may be
+    /// empty even when the stream isn't exhausted.
     fn read(&mut self) -> Result<Option<Vec<Point>>> {
-        let mut pxyz32 = vec![Default::default(); self.want as usize];
-        let mut pattributes = vec![Default::default(); self.want as usize];
-        let mut ptime = vec![Default::default(); self.want as usize];
-        let mut got = 0;
-        let mut end_of_frame = 0;
-
-        scanifc_try!(scanifc_sys::scanifc_point3dstream_read(
-            self.handle,
-            self.want,
-            pxyz32.as_mut_ptr(),
-            pattributes.as_mut_ptr(),
-            ptime.as_mut_ptr(),
-            &mut got,
-            &mut end_of_frame,
-        ));
-        Ok(if got == 0 && end_of_frame == 0 {
+        let (got, exhausted) = self.raw_read()?;
+        Ok(if exhausted {
             None
         } else {
             Some(
-                pxyz32
-                    .into_iter()
-                    .zip(pattributes.into_iter())
-                    .zip(ptime.into_iter())
-                    .take(got as usize)
-                    .map(|((xyz32, attributes), time)| {
-                        Point::from((xyz32, attributes, time))
+                (0..got)
+                    .map(|i| {
+                        Point::from((self.pxyz32[i], self.pattributes[i], self.ptime[i]))
                     })
+                    .filter(|point| self.matches(point))
                     .collect(),
             )
         })
     }
+
+    /// Returns whether `point` satisfies this stream's `PointFilter`, if any.
+    fn matches(&self, point: &Point) -> bool {
+        match self.filter {
+            Some(ref filter) => filter.matches(point),
+            None => true,
+        }
+    }
+
+    /// Reads up to `out.len()` points directly into `out`, returning how many were written.
+    ///
+    /// A return value of `0` signals the end of the stream. This drains the internal
+    /// `VecDeque` left over from a previous `read`/`Iterator` call first, and only issues a
+    /// fresh FFI read once `out` still has room, so it coexists with the existing `want`-based
+    /// buffering instead of bypassing it. Unlike `read` and the `Iterator` impl, no `Vec<Point>`
+    /// is allocated to satisfy the caller's own fixed buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::Point;
+    /// use scanifc::point3d::Stream;
+    /// let mut stream = Stream::from_path("data/scan.rxp").open().unwrap();
+    /// let mut buffer = vec![Point::default(); 1024];
+    /// let n = stream.read_into(&mut buffer).unwrap();
+    /// ```
+    pub fn read_into(&mut self, out: &mut [Point]) -> Result<usize> {
+        let mut written = 0;
+        while written < out.len() {
+            if let Some(point) = self.buffer.pop_front() {
+                out[written] = point;
+                written += 1;
+                continue;
+            }
+            let (got, exhausted) = self.raw_read()?;
+            if exhausted {
+                break;
+            }
+            for i in 0..got {
+                let point = Point::from((self.pxyz32[i], self.pattributes[i], self.ptime[i]));
+                if !self.matches(&point) {
+                    continue;
+                }
+                if written < out.len() {
+                    out[written] = point;
+                    written += 1;
+                } else {
+                    self.buffer.push_back(point);
+                }
+            }
+        }
+        Ok(written)
+    }
+
+    /// Issues a single FFI read into this stream's scratch buffers.
+    ///
+    /// Returns the number of points written to them, and whether the stream is exhausted (no
+    /// points and no end-of-frame marker came back).
+    fn raw_read(&mut self) -> Result<(usize, bool)> {
+        let mut got = 0;
+        let mut end_of_frame = 0;
+
+        match self.backend {
+            #[cfg(feature = "static-link")]
+            Backend::Linked => {
+                scanifc_try!(scanifc_sys::scanifc_point3dstream_read(
+                    self.handle,
+                    self.want,
+                    self.pxyz32.as_mut_ptr(),
+                    self.pattributes.as_mut_ptr(),
+                    self.ptime.as_mut_ptr(),
+                    &mut got,
+                    &mut end_of_frame,
+                ));
+            }
+            Backend::Dynamic(ref library) => {
+                let result = unsafe {
+                    (library.point3dstream_read)(
+                        self.handle,
+                        self.want,
+                        self.pxyz32.as_mut_ptr(),
+                        self.pattributes.as_mut_ptr(),
+                        self.ptime.as_mut_ptr(),
+                        &mut got,
+                        &mut end_of_frame,
+                    )
+                };
+                if result != 0 {
+                    return Err(Error::Scanifc(result, library.last_error()?));
+                }
+            }
+        }
+        Ok((got as usize, got == 0 && end_of_frame == 0))
+    }
 }
 
 impl Iterator for OpenStream {
     type Item = Result<Point>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(point) = self.buffer.pop_front() {
-            Some(Ok(point))
-        } else {
+        loop {
+            if let Some(point) = self.buffer.pop_front() {
+                return Some(Ok(point));
+            }
             match self.fill_buffer() {
-                Ok(Some(())) => self.next(),
-                Ok(None) => None,
-                Err(err) => Some(Err(err)),
+                Ok(Some(())) => continue,
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
             }
         }
     }
@@ -218,7 +681,11 @@ impl Iterator for OpenStream {
 
 impl Drop for OpenStream {
     fn drop(&mut self) {
-        unsafe { scanifc_sys::scanifc_point3dstream_close(self.handle) };
+        match self.backend {
+            #[cfg(feature = "static-link")]
+            Backend::Linked => unsafe { scanifc_sys::scanifc_point3dstream_close(self.handle) },
+            Backend::Dynamic(ref library) => unsafe { (library.point3dstream_close)(self.handle) },
+        };
     }
 }
 
@@ -238,6 +705,112 @@ impl Uri {
     }
 }
 
+/// Reads points from many already-open streams concurrently, one worker thread per stream.
+///
+/// Each point is tagged with the index of the stream (matching the order the streams were
+/// passed in, e.g. from `Stream::open_many`) it came from, so a caller can recover which file
+/// produced it.
+///
+/// # Examples
+///
+/// ```
+/// use scanifc::point3d::{read_many, Stream};
+/// let streams = Stream::open_many(&["data/scan.rxp"]).unwrap();
+/// for (index, result) in read_many(streams) {
+///     let point = result.unwrap();
+///     println!("stream {}: {:?}", index, point);
+/// }
+/// ```
+pub fn read_many(streams: Vec<OpenStream>) -> mpsc::IntoIter<(usize, Result<Point>)> {
+    let (sender, receiver) = mpsc::channel();
+    for (index, stream) in streams.into_iter().enumerate() {
+        let sender = sender.clone();
+        let stream = AssertSend(stream);
+        thread::spawn(move || {
+            let AssertSend(stream) = stream;
+            for result in stream {
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    receiver.into_iter()
+}
+
+/// Wraps a value that owns a raw FFI handle so it can cross the boundary into the single worker
+/// thread that reads it.
+///
+/// `OpenStream` holds a `scanifc_sys::point3dstream_handle`, which is a raw pointer and makes it
+/// `!Send` by default — the right default, since an FFI handle can't safely be touched from two
+/// threads at once. `into_async` and `read_many` each move one `OpenStream` into exactly one
+/// dedicated thread, which then has sole ownership of it for the rest of its lifetime and never
+/// hands it back, so crossing that one spawn boundary is sound.
+struct AssertSend<T>(T);
+
+unsafe impl<T> Send for AssertSend<T> {}
+
+/// Raises the process's open file descriptor soft limit toward its hard limit.
+///
+/// This is a best-effort operation: any failure to read or set the limit is swallowed, and
+/// callers proceed as if nothing happened.
+#[cfg(unix)]
+fn raise_file_descriptor_limit() {
+    use libc::{getrlimit, rlimit, setrlimit, RLIMIT_NOFILE};
+    use std::mem;
+
+    unsafe {
+        let mut limit: rlimit = mem::zeroed();
+        if getrlimit(RLIMIT_NOFILE, &mut limit) != 0 {
+            return;
+        }
+        let mut desired = limit.rlim_max;
+        if let Some(max_files_per_proc) = max_files_per_proc() {
+            desired = desired.min(max_files_per_proc);
+        }
+        if desired > limit.rlim_cur {
+            limit.rlim_cur = desired;
+            let _ = setrlimit(RLIMIT_NOFILE, &limit);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn raise_file_descriptor_limit() {}
+
+/// On Darwin, `setrlimit` silently refuses to raise `RLIMIT_NOFILE` above the
+/// `kern.maxfilesperproc` sysctl value, so we have to clamp our request to it ourselves.
+#[cfg(target_os = "macos")]
+fn max_files_per_proc() -> Option<libc::rlim_t> {
+    use libc::{c_void, sysctlbyname};
+    use std::ffi::CString;
+    use std::mem;
+    use std::ptr;
+
+    let name = CString::new("kern.maxfilesperproc").ok()?;
+    let mut value: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>();
+    let result = unsafe {
+        sysctlbyname(
+            name.as_ptr(),
+            &mut value as *mut _ as *mut c_void,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if result == 0 {
+        Some(value as libc::rlim_t)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn max_files_per_proc() -> Option<libc::rlim_t> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,6 +852,34 @@ mod tests {
         assert_eq!(1, stream.read().unwrap().unwrap().len());
     }
 
+    #[test]
+    fn stream_read_into() {
+        let mut stream = Stream::from_path("data/scan.rxp").want(1).open().unwrap();
+        let mut buffer = vec![Point::default(); 10];
+        assert_eq!(10, stream.read_into(&mut buffer).unwrap());
+    }
+
+    #[test]
+    fn stream_into_async() {
+        let async_stream = Stream::from_path("data/scan.rxp").into_async(4).unwrap();
+        let mut total = 0;
+        while let Some(points) = async_stream.recv().unwrap() {
+            total += points.len();
+        }
+        assert_eq!(24390, total);
+    }
+
+    #[test]
+    fn stream_filter() {
+        let filter = PointFilter::new().predicate(|point| point.amplitude > 0.0);
+        let stream = Stream::from_path("data/scan.rxp")
+            .filter(filter)
+            .open()
+            .unwrap();
+        let points = stream.into_points().unwrap();
+        assert!(points.iter().all(|point| point.amplitude > 0.0));
+    }
+
     #[test]
     fn stream_log() {
         let tempfile = NamedTempFile::new().unwrap();
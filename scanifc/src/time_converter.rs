@@ -0,0 +1,161 @@
+//! Reconstructs absolute timestamps from the PPS-synced, per-point `time` values a `Point`
+//! carries.
+//!
+//! RIEGL sensors record two different timeframes for `Point.time` (in seconds): seconds since
+//! stream start when the point isn't PPS-synced, and seconds within the current PPS second once
+//! it is. Resolving the latter to a wall-clock time requires counting how many PPS pulses
+//! (`Point`s with `is_pps_new` set) have gone by, which is state that has to be tracked across
+//! `read` calls rather than recomputed from a single point.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// How a `TimeConverter` resolves a point's raw `time` into a wall-clock timestamp.
+#[derive(Clone, Debug)]
+enum Conversion {
+    /// Leave point times as opaque, stream-relative seconds.
+    Relative,
+    /// Resolve point times to wall-clock time, anchored at the epoch.
+    AbsoluteFromEpoch(DateTime<Utc>),
+}
+
+/// Converts a `Point`'s raw `time` into a wall-clock `DateTime<Utc>`.
+///
+/// Threaded through a sequence of `Point::timestamp` calls (in the same order the points were
+/// read), a `TimeConverter` tracks the running PPS second count and whether a pulse has been
+/// observed yet, so it must not be shared between two streams being read concurrently.
+#[derive(Clone, Debug)]
+pub struct TimeConverter {
+    conversion: Conversion,
+    pps_second: u64,
+    pps_seen: bool,
+}
+
+impl TimeConverter {
+    /// Creates a converter that leaves point times relative to stream start.
+    ///
+    /// `Point::timestamp` always returns `None` for a `Relative` converter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scanifc::TimeConverter;
+    /// let converter = TimeConverter::relative();
+    /// ```
+    pub fn relative() -> TimeConverter {
+        TimeConverter {
+            conversion: Conversion::Relative,
+            pps_second: 0,
+            pps_seen: false,
+        }
+    }
+
+    /// Creates a converter that resolves point times to wall-clock time, treating `epoch` as the
+    /// instant the stream started.
+    ///
+    /// A point outside the PPS timeframe resolves to `epoch + time`; a point inside it resolves
+    /// to `epoch`, plus one second per PPS pulse observed so far, plus `time`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chrono;
+    /// # extern crate scanifc;
+    /// # fn main() {
+    /// use chrono::Utc;
+    /// use scanifc::TimeConverter;
+    /// let converter = TimeConverter::absolute_from_epoch(Utc::now());
+    /// # }
+    /// ```
+    pub fn absolute_from_epoch(epoch: DateTime<Utc>) -> TimeConverter {
+        TimeConverter {
+            conversion: Conversion::AbsoluteFromEpoch(epoch),
+            pps_second: 0,
+            pps_seen: false,
+        }
+    }
+
+    /// Resolves `time`, given whether the point that carried it is PPS-synced and whether it
+    /// marks the arrival of a fresh PPS pulse.
+    ///
+    /// Returns `None` when this converter is `Relative`, or when `is_time_in_pps_timeframe` is
+    /// `true` but no PPS pulse has been observed yet.
+    pub(crate) fn resolve(
+        &mut self,
+        time: f64,
+        is_time_in_pps_timeframe: bool,
+        is_pps_new: bool,
+    ) -> Option<DateTime<Utc>> {
+        let epoch = match self.conversion {
+            Conversion::Relative => return None,
+            Conversion::AbsoluteFromEpoch(epoch) => epoch,
+        };
+        if is_pps_new {
+            self.pps_seen = true;
+            self.pps_second += 1;
+        }
+        if is_time_in_pps_timeframe {
+            if !self.pps_seen {
+                return None;
+            }
+            let second_start = epoch + Duration::seconds(self.pps_second as i64);
+            Some(second_start + seconds_to_duration(time))
+        } else {
+            Some(epoch + seconds_to_duration(time))
+        }
+    }
+}
+
+fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::seconds(seconds.trunc() as i64)
+        + Duration::nanoseconds((seconds.fract() * 1e9).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn relative_is_always_none() {
+        let mut converter = TimeConverter::relative();
+        assert_eq!(None, converter.resolve(0., false, false));
+        assert_eq!(None, converter.resolve(1.5, true, true));
+    }
+
+    #[test]
+    fn absolute_before_first_pps_pulse_is_none() {
+        let epoch = Utc.ymd(2020, 1, 1).and_hms(0, 0, 0);
+        let mut converter = TimeConverter::absolute_from_epoch(epoch);
+        assert_eq!(None, converter.resolve(0.25, true, false));
+    }
+
+    #[test]
+    fn absolute_resolves_pps_rollover_sequence() {
+        let epoch = Utc.ymd(2020, 1, 1).and_hms(0, 0, 0);
+        let mut converter = TimeConverter::absolute_from_epoch(epoch);
+
+        // A point before the first PPS pulse, timed relative to stream start.
+        assert_eq!(
+            Some(epoch + Duration::milliseconds(500)),
+            converter.resolve(0.5, false, false)
+        );
+
+        // The first PPS pulse arrives, starting PPS second 1.
+        assert_eq!(
+            Some(epoch + Duration::seconds(1) + Duration::milliseconds(100)),
+            converter.resolve(0.1, true, true)
+        );
+
+        // A later point within the same PPS second, no new pulse.
+        assert_eq!(
+            Some(epoch + Duration::seconds(1) + Duration::milliseconds(250)),
+            converter.resolve(0.25, true, false)
+        );
+
+        // A second PPS pulse arrives, rolling over to PPS second 2.
+        assert_eq!(
+            Some(epoch + Duration::seconds(2)),
+            converter.resolve(0., true, true)
+        );
+    }
+}
@@ -26,15 +26,20 @@
 #![deny(missing_docs, missing_debug_implementations, missing_copy_implementations, trivial_casts,
         trivial_numeric_casts, unstable_features, unused_import_braces, unused_qualifications)]
 
+extern crate chrono;
 #[macro_use]
 extern crate failure;
+extern crate futures;
+extern crate libc;
 extern crate scanifc_sys;
 extern crate scanlib;
 
 mod point;
 mod reader;
 mod scanifc;
+mod time_scale;
 
 pub use scanlib::Inclination;
 pub use point::{EchoType, Point};
-pub use reader::Reader;
+pub use reader::{MultiReader, Reader};
+pub use time_scale::{TimeScale, Timestamped};
@@ -1,3 +1,5 @@
+use time_scale::Timestamped;
+
 /// An rxp point.
 #[derive(Clone, Copy, Debug)]
 pub struct Point {
@@ -60,6 +62,16 @@ pub enum EchoType {
     Last,
 }
 
+impl Timestamped for Point {
+    fn raw_time(&self) -> f64 {
+        self.time
+    }
+
+    fn has_fresh_pps(&self) -> bool {
+        self.with_fresh_pps
+    }
+}
+
 impl From<u16> for EchoType {
     fn from(n: u16) -> EchoType {
         match n & 3 {
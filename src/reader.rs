@@ -1,18 +1,44 @@
 use {Inclination, Point};
 use failure::Error;
+use futures::sync::mpsc;
+use futures::{Async, Future, Poll, Sink, Stream as FutureStream};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc as sync_mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use scanifc::{self, Stream};
 use scanlib::{self, Pointcloud};
 use std::collections::VecDeque;
+use time_scale::TimeScale;
 
 const DEFAULT_WANT: u32 = 1024;
 
+/// The number of in-flight batches buffered between the background reader thread and a
+/// `PointStream` or `InclinationStream` consumer.
+const DEFAULT_STREAM_BUFFER_BATCHES: usize = 4;
+
+/// The default number of rxp streams a `MultiReader` will keep open concurrently.
+const DEFAULT_CONCURRENCY: usize = 16;
+
+/// Wraps a value that owns a raw FFI handle so it can cross the boundary into the single worker
+/// thread that reads it.
+///
+/// `Stream` and `Pointcloud` each hold a raw pointer to an FFI handle, which makes them `!Send`
+/// by default — the right default, since an FFI handle can't safely be touched from two threads
+/// at once. `points_stream`, `inclinations_stream`, and `MultiReader::points` each move one of
+/// these into exactly one dedicated thread, which then has sole ownership of it for the rest of
+/// its lifetime and never hands it back, so crossing that one spawn boundary is sound.
+struct AssertSend<T>(T);
+
+unsafe impl<T> Send for AssertSend<T> {}
+
 /// Reads points and other information from rxp files.
 #[derive(Debug)]
 pub struct Reader {
     path: PathBuf,
     sync_to_pps: bool,
     want: u32,
+    time_scale: TimeScale,
 }
 
 /// An iterator over rxp points.
@@ -30,6 +56,61 @@ pub struct Inclinations {
     pointcloud: Pointcloud,
 }
 
+/// A non-blocking `futures::Stream` over rxp points.
+///
+/// The FFI reads happen on a dedicated background thread, which pushes decoded batches across a
+/// bounded channel. Dropping a `PointStream` drops its receiver, which causes the next send on
+/// the background thread to fail and the thread to exit, running `scanifc_point3dstream_close`
+/// as part of its `Stream`'s `Drop` implementation.
+#[derive(Debug)]
+pub struct PointStream {
+    buffer: VecDeque<Point>,
+    receiver: mpsc::Receiver<Result<Vec<Point>, scanifc::Error>>,
+}
+
+/// A non-blocking `futures::Stream` over inclination readings.
+///
+/// See `PointStream` for the threading and cancellation model.
+#[derive(Debug)]
+pub struct InclinationStream {
+    buffer: VecDeque<Inclination>,
+    receiver: mpsc::Receiver<Result<Vec<Inclination>, scanlib::Error>>,
+}
+
+/// Reads points from many rxp files concurrently.
+///
+/// Opening hundreds of rxp tiles at once routinely trips the OS's soft limit on open file
+/// descriptors, so a `MultiReader` raises that limit toward the hard limit before opening any
+/// streams, and bounds how many streams are open at once so the raised limit isn't immediately
+/// re-exhausted.
+#[derive(Debug)]
+pub struct MultiReader {
+    paths: Vec<PathBuf>,
+    sync_to_pps: bool,
+    want: u32,
+    concurrency: usize,
+}
+
+/// An error produced while reading one file in a `MultiReader` batch.
+///
+/// A per-file failure surfaces as one of these rather than aborting the whole batch.
+#[derive(Debug, Fail)]
+#[fail(display = "error reading {:?}: {}", path, error)]
+pub struct MultiReaderError {
+    /// The file that produced the error.
+    pub path: PathBuf,
+    /// The underlying error.
+    pub error: Error,
+}
+
+/// A merged iterator over the points of many rxp files, read concurrently.
+#[derive(Debug)]
+pub struct MultiPoints {
+    receiver: sync_mpsc::Receiver<Result<(PathBuf, Point), MultiReaderError>>,
+    concurrency: usize,
+    file_descriptor_limit: Option<u64>,
+}
+
 impl Reader {
     /// Creates a new reader for the provided path, with `sync_to_pps` set to false.
     ///
@@ -43,6 +124,7 @@ impl Reader {
             path: path.as_ref().to_path_buf(),
             sync_to_pps: false,
             want: DEFAULT_WANT,
+            time_scale: TimeScale::Raw,
         }
     }
 
@@ -73,6 +155,34 @@ impl Reader {
         self
     }
 
+    /// Sets the time scale used to interpret points' and inclinations' raw `time` values.
+    ///
+    /// Defaults to `TimeScale::Raw`, which preserves today's behavior. The configured scale is
+    /// available afterwards via `configured_time_scale`, for passing to `Timestamped::datetime`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rivlib::TimeScale;
+    /// let reader = rivlib::Reader::from_path("data/scan.rxp").time_scale(TimeScale::UnixSeconds);
+    /// ```
+    pub fn time_scale(mut self, time_scale: TimeScale) -> Reader {
+        self.time_scale = time_scale;
+        self
+    }
+
+    /// Returns the time scale configured on this reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let reader = rivlib::Reader::from_path("data/scan.rxp");
+    /// assert_eq!(&rivlib::TimeScale::Raw, reader.configured_time_scale());
+    /// ```
+    pub fn configured_time_scale(&self) -> &TimeScale {
+        &self.time_scale
+    }
+
     /// Returns an iterator over this reader's points.
     ///
     /// # Examples
@@ -110,6 +220,265 @@ impl Reader {
             pointcloud: Pointcloud::from_path(&self.path, self.sync_to_pps)?,
         })
     }
+
+    /// Returns a non-blocking stream over this reader's points.
+    ///
+    /// The blocking `scanifc_point3dstream_read` calls are moved onto a dedicated thread, and
+    /// decoded batches are pushed across a bounded channel so that a caller can `select!` across
+    /// many rxp files at once instead of blocking a thread per file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate futures;
+    /// # extern crate rivlib;
+    /// # fn main() {
+    /// use futures::{Future, Stream};
+    /// let reader = rivlib::Reader::from_path("data/scan.rxp");
+    /// let points = reader.points_stream().unwrap().collect().wait().unwrap();
+    /// # }
+    /// ```
+    pub fn points_stream(&self) -> Result<PointStream, Error> {
+        let stream = AssertSend(Stream::open(&self.path, self.sync_to_pps)?);
+        let want = self.want;
+        let (sender, receiver) = mpsc::channel(DEFAULT_STREAM_BUFFER_BATCHES);
+        thread::spawn(move || {
+            let AssertSend(mut stream) = stream;
+            let mut sender = sender;
+            loop {
+                match stream.read(want) {
+                    Ok(points) => {
+                        if points.is_empty() {
+                            break;
+                        }
+                        sender = match sender.send(Ok(points)).wait() {
+                            Ok(sender) => sender,
+                            Err(_) => break,
+                        };
+                    }
+                    Err(err) => {
+                        let _ = sender.send(Err(err)).wait();
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(PointStream {
+            buffer: VecDeque::new(),
+            receiver: receiver,
+        })
+    }
+
+    /// Returns a non-blocking stream over the inclinations in this rxp file.
+    ///
+    /// See `points_stream` for the threading and backpressure model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate futures;
+    /// # extern crate rivlib;
+    /// # fn main() {
+    /// use futures::{Future, Stream};
+    /// let reader = rivlib::Reader::from_path("data/scan.rxp");
+    /// let inclinations = reader.inclinations_stream().unwrap().collect().wait().unwrap();
+    /// # }
+    /// ```
+    pub fn inclinations_stream(&self) -> Result<InclinationStream, Error> {
+        let pointcloud = AssertSend(Pointcloud::from_path(&self.path, self.sync_to_pps)?);
+        let (sender, receiver) = mpsc::channel(DEFAULT_STREAM_BUFFER_BATCHES);
+        thread::spawn(move || {
+            let AssertSend(mut pointcloud) = pointcloud;
+            let mut sender = sender;
+            loop {
+                match pointcloud.read_inclinations() {
+                    Ok(Some(inclinations)) => {
+                        sender = match sender.send(Ok(inclinations)).wait() {
+                            Ok(sender) => sender,
+                            Err(_) => break,
+                        };
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        let _ = sender.send(Err(err)).wait();
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(InclinationStream {
+            buffer: VecDeque::new(),
+            receiver: receiver,
+        })
+    }
+}
+
+impl MultiReader {
+    /// Creates a new multi-file reader for the provided paths, with `sync_to_pps` set to false.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let reader = rivlib::MultiReader::from_paths(vec!["data/scan.rxp"]);
+    /// ```
+    pub fn from_paths<P: AsRef<Path>>(paths: Vec<P>) -> MultiReader {
+        MultiReader {
+            paths: paths.iter().map(|path| path.as_ref().to_path_buf()).collect(),
+            sync_to_pps: false,
+            want: DEFAULT_WANT,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Sets the sync-to-pps value used when opening each stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let reader = rivlib::MultiReader::from_paths(vec!["data/scan.rxp"]).sync_to_pps(true);
+    /// ```
+    pub fn sync_to_pps(mut self, sync_to_pps: bool) -> MultiReader {
+        self.sync_to_pps = sync_to_pps;
+        self
+    }
+
+    /// Sets the number of points wanted for each read of each underlying stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let reader = rivlib::MultiReader::from_paths(vec!["data/scan.rxp"]).want(10);
+    /// ```
+    pub fn want(mut self, want: u32) -> MultiReader {
+        self.want = want;
+        self
+    }
+
+    /// Sets the maximum number of streams this reader will hold open at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let reader = rivlib::MultiReader::from_paths(vec!["data/scan.rxp"]).concurrency(4);
+    /// ```
+    pub fn concurrency(mut self, concurrency: usize) -> MultiReader {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Raises the process's file descriptor limit, then returns a merged iterator over every
+    /// file's points.
+    ///
+    /// A failure to open or read one file surfaces as a `MultiReaderError` for that file alone;
+    /// the rest of the batch keeps going.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let reader = rivlib::MultiReader::from_paths(vec!["data/scan.rxp"]);
+    /// let points = reader.points().filter_map(|r| r.ok()).collect::<Vec<_>>();
+    /// ```
+    pub fn points(&self) -> MultiPoints {
+        let file_descriptor_limit = raise_file_descriptor_limit();
+        let concurrency = self.concurrency.min(self.paths.len().max(1));
+        let queue = Arc::new(Mutex::new(self.paths.clone()));
+        let (sender, receiver) = sync_mpsc::sync_channel(DEFAULT_STREAM_BUFFER_BATCHES);
+        let sync_to_pps = self.sync_to_pps;
+        let want = self.want;
+        for _ in 0..concurrency {
+            let queue = queue.clone();
+            let sender = sender.clone();
+            // Unlike `points_stream`/`inclinations_stream`, this `Stream` is opened, read, and
+            // dropped entirely inside the spawned closure below rather than being moved in from
+            // the caller, so it's never part of the closure's captured environment and the
+            // `!Send` raw FFI handle it carries never has to cross a thread boundary.
+            thread::spawn(move || loop {
+                let path = match queue.lock().unwrap().pop() {
+                    Some(path) => path,
+                    None => break,
+                };
+                let mut stream = match Stream::open(&path, sync_to_pps) {
+                    Ok(stream) => stream,
+                    Err(error) => {
+                        let _ = sender.send(Err(MultiReaderError { path: path, error: error }));
+                        continue;
+                    }
+                };
+                loop {
+                    match stream.read(want) {
+                        Ok(points) => {
+                            if points.is_empty() {
+                                break;
+                            }
+                            for point in points {
+                                if sender.send(Ok((path.clone(), point))).is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        Err(error) => {
+                            let _ = sender.send(Err(MultiReaderError {
+                                path: path.clone(),
+                                error: error.into(),
+                            }));
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+        MultiPoints {
+            receiver: receiver,
+            concurrency: concurrency,
+            file_descriptor_limit: file_descriptor_limit,
+        }
+    }
+}
+
+impl MultiPoints {
+    /// Returns the number of streams this batch is holding open concurrently.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Returns the file descriptor limit in effect after this batch raised it, or `None` if the
+    /// limit couldn't be determined or raised (e.g. on a non-Unix platform).
+    pub fn file_descriptor_limit(&self) -> Option<u64> {
+        self.file_descriptor_limit
+    }
+}
+
+impl Iterator for MultiPoints {
+    type Item = Result<(PathBuf, Point), MultiReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+#[cfg(unix)]
+fn raise_file_descriptor_limit() -> Option<u64> {
+    use libc::{getrlimit, rlimit, setrlimit, RLIMIT_NOFILE};
+    use std::mem;
+
+    unsafe {
+        let mut limit: rlimit = mem::zeroed();
+        if getrlimit(RLIMIT_NOFILE, &mut limit) != 0 {
+            return None;
+        }
+        if limit.rlim_max > limit.rlim_cur {
+            limit.rlim_cur = limit.rlim_max;
+            if setrlimit(RLIMIT_NOFILE, &limit) != 0 {
+                return None;
+            }
+        }
+        Some(u64::from(limit.rlim_cur))
+    }
+}
+
+#[cfg(not(unix))]
+fn raise_file_descriptor_limit() -> Option<u64> {
+    None
 }
 
 impl Iterator for Points {
@@ -153,6 +522,44 @@ impl Iterator for Inclinations {
     }
 }
 
+impl FutureStream for PointStream {
+    type Item = Point;
+    type Error = scanifc::Error;
+
+    fn poll(&mut self) -> Poll<Option<Point>, scanifc::Error> {
+        loop {
+            if let Some(point) = self.buffer.pop_front() {
+                return Ok(Async::Ready(Some(point)));
+            }
+            match self.receiver.poll() {
+                Ok(Async::Ready(Some(Ok(points)))) => self.buffer.extend(points),
+                Ok(Async::Ready(Some(Err(err)))) => return Err(err),
+                Ok(Async::Ready(None)) | Err(()) => return Ok(Async::Ready(None)),
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+            }
+        }
+    }
+}
+
+impl FutureStream for InclinationStream {
+    type Item = Inclination;
+    type Error = scanlib::Error;
+
+    fn poll(&mut self) -> Poll<Option<Inclination>, scanlib::Error> {
+        loop {
+            if let Some(inclination) = self.buffer.pop_front() {
+                return Ok(Async::Ready(Some(inclination)));
+            }
+            match self.receiver.poll() {
+                Ok(Async::Ready(Some(Ok(inclinations)))) => self.buffer.extend(inclinations),
+                Ok(Async::Ready(Some(Err(err)))) => return Err(err),
+                Ok(Async::Ready(None)) | Err(()) => return Ok(Async::Ready(None)),
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +573,38 @@ mod tests {
     fn sync_to_pps() {
         Reader::from_path("data/scan.rxp").sync_to_pps(true);
     }
+
+    #[test]
+    fn points_stream() {
+        let reader = Reader::from_path("data/scan.rxp");
+        let points = reader.points_stream().unwrap().collect().wait().unwrap();
+        assert!(!points.is_empty());
+    }
+
+    #[test]
+    fn inclinations_stream() {
+        let reader = Reader::from_path("data/scan.rxp");
+        let inclinations = reader
+            .inclinations_stream()
+            .unwrap()
+            .collect()
+            .wait()
+            .unwrap();
+        assert!(!inclinations.is_empty());
+    }
+
+    #[test]
+    fn multi_reader_points() {
+        let reader = MultiReader::from_paths(vec!["data/scan.rxp"]);
+        let points = reader.points().filter_map(|r| r.ok()).collect::<Vec<_>>();
+        assert!(!points.is_empty());
+    }
+
+    #[test]
+    fn multi_reader_reports_concurrency_and_file_descriptor_limit() {
+        let reader = MultiReader::from_paths(vec!["data/scan.rxp"]);
+        let points = reader.points();
+        assert_eq!(1, points.concurrency());
+        let _ = points.file_descriptor_limit();
+    }
 }
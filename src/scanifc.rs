@@ -30,6 +30,9 @@ macro_rules! scanifc_try {
 #[derive(Debug)]
 pub struct Stream {
     handle: *mut scanifc_sys::point3dstream,
+    points: Vec<scanifc_sys::scanifc_xyz32_t>,
+    attributes: Vec<scanifc_sys::scanifc_attributes_t>,
+    times: Vec<u64>,
 }
 
 #[derive(Debug, Fail)]
@@ -53,14 +56,21 @@ impl Stream {
                 &mut handle
             ));
         }
-        Ok(Stream { handle: handle })
+        Ok(Stream {
+            handle: handle,
+            points: Vec::new(),
+            attributes: Vec::new(),
+            times: Vec::new(),
+        })
     }
 
     pub fn read(&mut self, want: u32) -> Result<Vec<Point>, Error> {
         let want_usize = want as usize;
-        let mut points = vec![Default::default(); want_usize];
-        let mut attributes = vec![Default::default(); want_usize];
-        let mut times = vec![Default::default(); want_usize];
+        if self.points.len() != want_usize {
+            self.points.resize(want_usize, Default::default());
+            self.attributes.resize(want_usize, Default::default());
+            self.times.resize(want_usize, Default::default());
+        }
         let mut got = 0;
         // We ignore end of frame b/c it doesn't seem to be set.
         let mut _end_of_frame = 0;
@@ -68,20 +78,17 @@ impl Stream {
             scanifc_try!(scanifc_sys::scanifc_point3dstream_read(
                 self.handle,
                 want,
-                points.as_mut_ptr(),
-                attributes.as_mut_ptr(),
-                times.as_mut_ptr(),
+                self.points.as_mut_ptr(),
+                self.attributes.as_mut_ptr(),
+                self.times.as_mut_ptr(),
                 &mut got,
                 &mut _end_of_frame
             ));
         }
         // TODO test point mappings
-        let points = points
-            .into_iter()
-            .zip(attributes)
-            .zip(times)
-            .take(got as usize)
-            .map(|((point, attribute), time)| Point {
+        let points = (0..got as usize)
+            .map(|i| (self.points[i], self.attributes[i], self.times[i]))
+            .map(|(point, attribute, time)| Point {
                 x: point.x,
                 y: point.y,
                 z: point.z,
@@ -0,0 +1,127 @@
+//! Pluggable conversion from a raw, epoch-less `time` value into a typed timestamp.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use Inclination;
+
+/// A named way to interpret the raw `time` value carried by a `Point` or `Inclination`.
+///
+/// `Point.time` and `Inclination.time` are bare seconds with no documented epoch; a `TimeScale`
+/// pins down what that value actually means so it can be turned into a `chrono::DateTime<Utc>`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeScale {
+    /// Leave `time` uninterpreted. This preserves today's behavior, so `datetime` always
+    /// returns `None` for this scale.
+    Raw,
+
+    /// `time` is GPS seconds of week, relative to the given GPS `week` number.
+    GpsSecondsOfWeek {
+        /// The GPS week number that `time` is counted from.
+        week: u32,
+        /// The number of leap seconds to subtract to arrive at UTC.
+        leap_seconds: i64,
+    },
+
+    /// `time` is seconds since the Unix epoch (1970-01-01T00:00:00Z).
+    UnixSeconds,
+
+    /// `time` is seconds since the Unix epoch, additionally formatted with the given
+    /// `chrono::format::strftime` format string by `Timestamped::format_time`.
+    UtcFmt(String),
+}
+
+/// A reading that carries a raw, scale-dependent `time` value.
+///
+/// Implemented for `Point` and `scanlib::Inclination` so both can be converted with the same
+/// `TimeScale`.
+pub trait Timestamped {
+    /// The raw time value, in seconds, as recorded by the sensor.
+    fn raw_time(&self) -> f64;
+
+    /// Whether this reading is synced to a PPS pulse no older than 1.5 seconds.
+    ///
+    /// Readings that don't carry PPS information (e.g. inclinations) are always considered
+    /// fresh.
+    fn has_fresh_pps(&self) -> bool {
+        true
+    }
+
+    /// Converts this reading's raw time into a UTC timestamp using `scale`.
+    ///
+    /// Returns `None` if `scale` is `TimeScale::Raw`, or if `scale` needs a fresh PPS signal and
+    /// this reading doesn't have one.
+    fn datetime(&self, scale: &TimeScale) -> Option<DateTime<Utc>> {
+        if scale.requires_pps() && !self.has_fresh_pps() {
+            return None;
+        }
+        scale.convert(self.raw_time())
+    }
+
+    /// Formats this reading's timestamp using `scale`, if `scale` carries a format string.
+    fn format_time(&self, scale: &TimeScale) -> Option<String> {
+        match *scale {
+            TimeScale::UtcFmt(ref format) => self.datetime(scale).map(|dt| dt.format(format).to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Timestamped for Inclination {
+    fn raw_time(&self) -> f64 {
+        self.time
+    }
+}
+
+impl TimeScale {
+    fn requires_pps(&self) -> bool {
+        match *self {
+            TimeScale::GpsSecondsOfWeek { .. } => true,
+            _ => false,
+        }
+    }
+
+    fn convert(&self, time: f64) -> Option<DateTime<Utc>> {
+        match *self {
+            TimeScale::Raw => None,
+            TimeScale::GpsSecondsOfWeek { week, leap_seconds } => {
+                let epoch = Utc.ymd(1980, 1, 6).and_hms(0, 0, 0) + Duration::weeks(i64::from(week));
+                Some(epoch + seconds_to_duration(time - leap_seconds as f64))
+            }
+            TimeScale::UnixSeconds | TimeScale::UtcFmt(_) => {
+                Some(Utc.timestamp(0, 0) + seconds_to_duration(time))
+            }
+        }
+    }
+}
+
+fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::seconds(seconds.trunc() as i64) + Duration::nanoseconds((seconds.fract() * 1e9).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_is_always_none() {
+        assert_eq!(None, TimeScale::Raw.convert(1234.5));
+    }
+
+    #[test]
+    fn unix_seconds_converts_from_the_epoch() {
+        let scale = TimeScale::UnixSeconds;
+        assert_eq!(
+            Some(Utc.timestamp(0, 0) + Duration::milliseconds(1500)),
+            scale.convert(1.5)
+        );
+    }
+
+    #[test]
+    fn gps_seconds_of_week_converts_from_the_gps_epoch_and_subtracts_leap_seconds() {
+        let scale = TimeScale::GpsSecondsOfWeek {
+            week: 0,
+            leap_seconds: 18,
+        };
+        let expected = Utc.ymd(1980, 1, 6).and_hms(0, 0, 0) + Duration::seconds(3600 - 18);
+        assert_eq!(Some(expected), scale.convert(3600.));
+    }
+}